@@ -1,16 +1,32 @@
 use std::cmp::Ordering;
 
-use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono::{Duration, TimeZone, Utc};
+use chrono_tz::{Tz, UTC};
 use rrule::RRuleSet;
 
+use super::moment::Moment;
+
+/// The publication status of an event, per the iCalendar `STATUS` property.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Status {
+    Confirmed,
+    Tentative,
+    Cancelled,
+}
+
 #[derive(Debug)]
 pub struct Event {
     pub id: String,
     pub summary: Option<String>,
     pub location: Option<String>,
     pub description: Option<String>,
-    pub start: DateTime<Utc>,
-    pub end: DateTime<Utc>,
+    pub start: Moment,
+    pub end: Moment,
+    pub status: Status,
+
+    /// The timezone `start`/`end` (and thus the RRULE) originate in, so
+    /// recurrences expand against local wall-clock time across DST boundaries.
+    pub tz: Tz,
     pub rrule: Option<RRuleSet>,
 }
 
@@ -21,14 +37,16 @@ impl Event {
             summary: None,
             location: None,
             description: None,
-            start: Utc.timestamp(0, 0),
-            end: Utc.timestamp(0, 0),
+            start: Moment::DateTime(Utc.timestamp(0, 0)),
+            end: Moment::DateTime(Utc.timestamp(0, 0)),
+            status: Status::Confirmed,
+            tz: UTC,
             rrule: None,
         }
     }
 
     pub fn duration(&self) -> Duration {
-        self.end - self.start
+        self.end.to_utc() - self.start.to_utc()
     }
 }
 
@@ -1,4 +1,5 @@
-use super::event::Event;
+use super::event::{Event, Status};
+use super::moment::Moment;
 use anyhow::Result;
 use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
 use chrono_tz::{Tz, UTC};
@@ -92,16 +93,34 @@ where
                     "DESCRIPTION" => event.description = prop.value,
                     "SUMMARY" => event.summary = prop.value,
                     "LOCATION" => event.location = prop.value,
+                    "STATUS" => {
+                        if let Some(value) = prop.value {
+                            event.status = match value.as_ref() {
+                                "CANCELLED" => Status::Cancelled,
+                                "TENTATIVE" => Status::Tentative,
+                                _ => Status::Confirmed,
+                            };
+                        }
+                    }
                     "DTSTART" => {
                         let dt_str = prop.value.unwrap();
-                        event.start = parse_datetime(&dt_str, get_tz(&prop.params))?;
+                        // `VALUE=DATE` (no `T` component) yields `Moment::Date`,
+                        // otherwise a timed `Moment::DateTime`.
+                        event.start = Moment::parse(&dt_str, get_tz(&prop.params))?;
+
+                        // Remember the originating timezone so recurrences can
+                        // be expanded against local wall-clock time.
+                        if let Some(tz) = get_tz(&prop.params) {
+                            event.tz = tz.parse().unwrap();
+                        }
 
                         // Reconstruct raw DTSTART line for use in RRULE
-                        dtstart = Some(reconstruct_datetime(&event.start, get_tz(&prop.params)));
+                        dtstart =
+                            Some(reconstruct_datetime(&event.start.to_utc(), get_tz(&prop.params)));
                     }
                     "DTEND" => {
                         let dt_str = prop.value.unwrap();
-                        event.end = parse_datetime(&dt_str, get_tz(&prop.params))?;
+                        event.end = Moment::parse(&dt_str, get_tz(&prop.params))?;
                     }
                     "RRULE" => {
                         // Kind of hacky, but the `rrule` crate doesn't provide
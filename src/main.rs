@@ -1,5 +1,7 @@
 mod event;
 mod ics;
+mod moment;
+mod schedule;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -9,17 +11,20 @@ use std::{
 
 use ansi_term::{Color, Style};
 use anyhow::{Context, Result};
-use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, Utc};
+use chrono::{Date, DateTime, Datelike, Duration, Local, NaiveDate, Utc};
 use chrono_tz::UTC;
-use event::Event;
+use event::{Event, Status};
 use expanduser::expanduser;
 use fs_err::{self as fs, File};
 use ics::parse_ics;
+use moment::Moment;
+use schedule::Schedule;
 
 const FORECAST_DAYS: i64 = 5;
 const REMINDER_MINUTES: i64 = 10;
 const REMINDER_REFRESH: u64 = 120; // seconds
 const UPDATE_EVERY: u64 = 5; // update every n reminder refresh intervals
+const SYNC_HORIZON_DAYS: i64 = 30; // how far ahead CalDAV syncs fetch events
 
 /// Read all events from local ics files.
 fn load_events() -> Result<Vec<Event>> {
@@ -44,15 +49,34 @@ fn load_upcoming_events(since: DateTime<Utc>, horizon: Duration) -> Result<Vec<E
     let mut upcoming: Vec<Event> = events
         .into_iter()
         .filter_map(|mut event| {
+            // Cancelled occurrences should never surface.
+            if event.status == Status::Cancelled {
+                return None;
+            }
             match &event.rrule {
                 Some(rrule) => {
-                    let next = rrule.after(since.with_timezone(&UTC), true);
+                    // Iterate in the event's own timezone so a 09:00 local
+                    // meeting stays at 09:00 local across DST transitions,
+                    // rather than drifting by an hour when folded to UTC.
+                    let next = rrule.after(since.with_timezone(&event.tz), true);
                     if let Some(next_occur) = next {
                         if next_occur <= end.with_timezone(&UTC) {
-                            // Change event start to the next occurrence
+                            // Change event start to the next occurrence,
+                            // preserving whether it is an all-day or timed event.
                             let duration = event.duration();
-                            event.start = next_occur.with_timezone(&Utc);
-                            event.end = event.start + duration;
+                            let next_start = next_occur.with_timezone(&Utc);
+                            if let Moment::Date(_) = event.start {
+                                // All-day occurrences are keyed by their local
+                                // calendar date, matching how they're grouped.
+                                let start_date = next_start.with_timezone(&Local).date_naive();
+                                let end_date =
+                                    (next_start + duration).with_timezone(&Local).date_naive();
+                                event.start = Moment::Date(Date::from_utc(start_date, Utc));
+                                event.end = Moment::Date(Date::from_utc(end_date, Utc));
+                            } else {
+                                event.start = Moment::DateTime(next_start);
+                                event.end = Moment::DateTime(next_start + duration);
+                            }
                             return Some(event);
                         }
                     }
@@ -60,7 +84,7 @@ fn load_upcoming_events(since: DateTime<Utc>, horizon: Duration) -> Result<Vec<E
                 }
                 None => {
                     // TODO check end
-                    if event.start >= since && event.start <= end {
+                    if event.start.to_utc() >= since && event.start.to_utc() <= end {
                         Some(event)
                     } else {
                         None
@@ -76,18 +100,35 @@ fn load_upcoming_events(since: DateTime<Utc>, horizon: Duration) -> Result<Vec<E
 
 /// View upcoming events for the next `days` days.
 fn view(days: i64) -> Result<()> {
-    // Treat "now" as the start of today (local time, but as UTC),
-    // b/c if we're e.g. 1 minute into an event we still want to see it
-    let now = Local::now()
-        .with_time(NaiveTime::from_hms_opt(0, 0, 0).expect("Valid"))
-        .unwrap();
-    let upcoming = load_upcoming_events(now.with_timezone(&Utc), Duration::days(days))?;
+    let today = Local::now().date_naive();
+    view_range(today, today + Duration::days(days - 1))
+}
+
+/// View events grouped by day across `[start, end]` (inclusive). Day labels
+/// stay relative to the current date, so viewing a past or future span still
+/// reads as "Today / Tomorrow / N days".
+fn view_range(start: NaiveDate, end: NaiveDate) -> Result<()> {
+    let today = Local::now().date_naive();
+
+    // Treat the window as starting at the start of `start` (local time, but as
+    // UTC), b/c if we're e.g. 1 minute into an event we still want to see it.
+    let since = start
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_local_timezone(Local)
+        .unwrap()
+        .with_timezone(&Utc);
+    let until = (end + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_local_timezone(Local)
+        .unwrap()
+        .with_timezone(&Utc);
+    let upcoming = load_upcoming_events(since, until - since)?;
 
     let mut byday: HashMap<NaiveDate, Vec<Event>> = HashMap::default();
     for event in upcoming {
-        let events = byday
-            .entry(event.start.with_timezone(&Local).date_naive())
-            .or_default();
+        let events = byday.entry(event.start.date_naive()).or_default();
         events.push(event);
     }
 
@@ -95,16 +136,20 @@ fn view(days: i64) -> Result<()> {
         .on(Color::RGB(36, 34, 186))
         .fg(Color::RGB(255, 255, 255));
     let summary_style = Style::new().underline();
+    let tentative_style = Style::new().italic().dimmed();
     let desc_style = Style::new().fg(Color::RGB(191, 190, 212));
-    for i in 0..days {
-        let date = (now + Duration::days(i)).date_naive();
+    let mut date = start;
+    while date <= end {
+        let offset = (date - today).num_days();
         let date_str = date.format("%a %b %e").to_string();
-        let date_str = if i == 0 {
+        let date_str = if offset == 0 {
             format!("{}\tToday", date_str)
-        } else if i == 1 {
+        } else if offset == 1 {
             format!("{}\tTomorrow", date_str)
+        } else if offset > 0 {
+            format!("{}\t{} days", date_str, offset)
         } else {
-            format!("{}\t{} days", date_str, i)
+            format!("{}\t{} days ago", date_str, -offset)
         };
         println!("{}", date_style.paint(date_str));
 
@@ -112,16 +157,18 @@ fn view(days: i64) -> Result<()> {
             Some(events) => {
                 for event in events {
                     // Print out single event
-                    if (event.end - event.start).num_hours() == 24 {
+                    if let Moment::Date(_) = event.start {
                         println!("{}", Color::Green.paint("All Day"));
                     } else {
-                        let start_str = event.start.with_timezone(&Local).format("%H:%M");
-                        let end_str_fmt = if event.start.day() == event.end.day() {
+                        let start = event.start.to_utc();
+                        let end = event.end.to_utc();
+                        let start_str = start.with_timezone(&Local).format("%H:%M");
+                        let end_str_fmt = if start.day() == end.day() {
                             "%H:%M"
                         } else {
                             "%a %b %e %H:%M"
                         };
-                        let end_str = event.end.with_timezone(&Local).format(end_str_fmt);
+                        let end_str = end.with_timezone(&Local).format(end_str_fmt);
                         println!(
                             "{} - {}",
                             Color::Green.paint(start_str.to_string()),
@@ -129,7 +176,12 @@ fn view(days: i64) -> Result<()> {
                         );
                     }
                     if let Some(summary) = &event.summary {
-                        println!("{}", summary_style.paint(summary));
+                        if event.status == Status::Tentative {
+                            let summary = format!("{} (tentative)", summary);
+                            println!("{}", tentative_style.paint(summary));
+                        } else {
+                            println!("{}", summary_style.paint(summary));
+                        }
                     }
                     if let Some(location) = &event.location {
                         println!("{}", location);
@@ -145,20 +197,61 @@ fn view(days: i64) -> Result<()> {
                 println!("No events\n");
             }
         }
+        date = date.succ_opt().unwrap();
     }
     Ok(())
 }
 
-/// Send a reminder for events starting in the next n minutes.
-fn remind(reminded: &mut HashSet<String>, remind_before: Duration) -> Result<()> {
+/// Read the optional reminder `Schedule` from `~/.config/agenda/config`,
+/// declared as a `reminders = <OnCalendar expression>` line.
+fn load_reminder_schedule() -> Result<Option<Schedule>> {
+    let config_path = expanduser("~/.config/agenda/config")?;
+    if !config_path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(&config_path)?;
+    for line in contents.lines() {
+        if let Some((key, value)) = line.split_once('=') {
+            if key.trim() == "reminders" {
+                return Ok(Some(Schedule::parse(value.trim())?));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Send a reminder for events starting in the next n minutes, unless the
+/// current time falls outside the configured reminder `schedule`.
+fn remind(
+    reminded: &mut HashSet<String>,
+    remind_before: Duration,
+    schedule: &Option<Schedule>,
+    poll: Duration,
+) -> Result<()> {
+    // Suppress reminders unless an allowed instant fell within the poll window
+    // that just elapsed, so a quarter-hour rule still fires despite the loop
+    // only waking every `poll`.
+    if let Some(schedule) = schedule {
+        let now = Local::now().naive_local();
+        match schedule.next_after(now - poll) {
+            Some(allowed) if allowed <= now => {}
+            _ => return Ok(()),
+        }
+    }
+
     let now = Utc::now();
     let upcoming = load_upcoming_events(now, remind_before)?;
     for event in upcoming {
+        // Only remind about events the organizer has actually confirmed.
+        if event.status != Status::Confirmed {
+            continue;
+        }
         if !reminded.contains(&event.id) {
             Command::new("notify-send")
                 .arg(
                     event
                         .start
+                        .to_utc()
                         .with_timezone(&Local)
                         .format("%H:%M")
                         .to_string(),
@@ -184,19 +277,83 @@ fn download(url: &str, path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Build the `calendar-query` body requesting only `VEVENT`s whose
+/// `time-range` overlaps `[start, end]`.
+fn calendar_query_body(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    let fmt = "%Y%m%dT%H%M%SZ";
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8" ?>
+<C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop>
+    <C:calendar-data/>
+  </D:prop>
+  <C:filter>
+    <C:comp-filter name="VCALENDAR">
+      <C:comp-filter name="VEVENT">
+        <C:time-range start="{}" end="{}"/>
+      </C:comp-filter>
+    </C:comp-filter>
+  </C:filter>
+</C:calendar-query>"#,
+        start.format(fmt),
+        end.format(fmt)
+    )
+}
+
+/// Sync a CalDAV collection, fetching only events in `[now, now + horizon]`
+/// via a `calendar-query` REPORT and writing the returned ICS to `path`.
+fn caldav_sync(url: &str, horizon: Duration, path: &Path) -> Result<()> {
+    let start = Utc::now();
+    let body = calendar_query_body(start, start + horizon);
+    let resp = ureq::request("REPORT", url)
+        .set("Depth", "1")
+        .set("Content-Type", "application/xml")
+        .send_string(&body)
+        .with_context(|| format!("Failed to REPORT on '{}'", &url))?;
+
+    let xml = resp.into_string()?;
+    let doc = roxmltree::Document::parse(&xml)
+        .with_context(|| "Failed to parse CalDAV multistatus response")?;
+
+    // Concatenate every `calendar-data` payload into a single ICS file,
+    // which `parse_ics` then reads just like a downloaded calendar.
+    let mut ics = String::new();
+    for node in doc
+        .descendants()
+        .filter(|n| n.tag_name().name() == "calendar-data")
+    {
+        for text in node.descendants().filter_map(|n| n.text()) {
+            ics.push_str(text);
+        }
+        ics.push('\n');
+    }
+
+    let mut file = File::create(path)?;
+    std::io::Write::write_all(&mut file, ics.as_bytes())
+        .with_context(|| "Error while writing CalDAV calendar")?;
+
+    Ok(())
+}
+
 /// Re-download the iCal files.
 fn refresh() -> Result<()> {
     let dir = expanduser("~/.config/agenda")?;
     let config_path = dir.join("calendars");
     let contents = fs::read_to_string(&config_path)?;
+    let horizon = Duration::days(SYNC_HORIZON_DAYS);
     for line in contents.lines() {
         if line.is_empty() {
             continue;
         }
 
-        let (name, url) = line.split_once(';').unwrap();
+        // Entries are either `name;url` for a plain ICS download or
+        // `name;caldav;url` for a server-side-filtered CalDAV sync.
+        let (name, rest) = line.split_once(';').unwrap();
         let path = dir.join(format!("{name}.ics"));
-        download(url, &path)?;
+        match rest.split_once(';') {
+            Some(("caldav", url)) => caldav_sync(url, horizon, &path)?,
+            _ => download(rest, &path)?,
+        }
     }
     Ok(())
 }
@@ -208,13 +365,17 @@ fn main() -> Result<()> {
         .expect("No command specified. Use 'view', 'refresh', or 'remind'.");
 
     match cmd.as_str() {
-        "view" => {
-            let days = args
-                .next()
-                .and_then(|v| v.parse().ok())
-                .unwrap_or(FORECAST_DAYS);
-            view(days)?
-        }
+        "view" => match (args.next(), args.next()) {
+            // Two args name an explicit `YYYY-MM-DD YYYY-MM-DD` window...
+            (Some(start), Some(end)) => {
+                let start = NaiveDate::parse_from_str(&start, "%Y-%m-%d")?;
+                let end = NaiveDate::parse_from_str(&end, "%Y-%m-%d")?;
+                view_range(start, end)?
+            }
+            // ...while a single number keeps the rolling "next N days" view.
+            (Some(days), None) => view(days.parse().unwrap_or(FORECAST_DAYS))?,
+            _ => view(FORECAST_DAYS)?,
+        },
         "remind" => {
             let mut reminded = HashSet::new();
             let remind_mins = args
@@ -222,7 +383,9 @@ fn main() -> Result<()> {
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(REMINDER_MINUTES);
             let remind_before = Duration::minutes(remind_mins);
+            let schedule = load_reminder_schedule()?;
             let sleep_dur = std::time::Duration::new(REMINDER_REFRESH, 0);
+            let poll = Duration::seconds(REMINDER_REFRESH as i64);
             let mut refresh_count = 0;
             loop {
                 refresh_count += 1;
@@ -230,7 +393,7 @@ fn main() -> Result<()> {
                     refresh()?;
                     refresh_count = 0;
                 }
-                remind(&mut reminded, remind_before)?;
+                remind(&mut reminded, remind_before, &schedule, poll)?;
                 std::thread::sleep(sleep_dur);
             }
         }
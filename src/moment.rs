@@ -1,6 +1,6 @@
 use thiserror::Error;
 use chrono_tz::{Tz, UTC};
-use chrono::{DateTime, Date, TimeZone, Utc};
+use chrono::{Date, DateTime, Datelike, Local, NaiveDate, TimeZone, Utc};
 use std::cmp::{Ordering, Ord};
 
 #[derive(Error, Debug)]
@@ -48,6 +48,26 @@ impl Moment {
             Ok(Moment::Date(d))
         }
     }
+
+    /// The moment as an instant in UTC. Date-only values resolve to local
+    /// midnight (as UTC), so all-day events span the viewer's full day.
+    pub fn to_utc(&self) -> DateTime<Utc> {
+        match self {
+            Moment::DateTime(dt) => *dt,
+            Moment::Date(d) => Local
+                .with_ymd_and_hms(d.year(), d.month(), d.day(), 0, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+
+    /// The local calendar date this moment falls on, for grouping by day.
+    pub fn date_naive(&self) -> NaiveDate {
+        match self {
+            Moment::DateTime(dt) => dt.with_timezone(&Local).date_naive(),
+            Moment::Date(d) => d.naive_utc(),
+        }
+    }
 }
 
 impl Ord for Moment {
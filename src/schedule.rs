@@ -0,0 +1,255 @@
+use chrono::{Datelike, Duration, NaiveDateTime, Timelike};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ScheduleError {
+    #[error("Empty calendar field")]
+    EmptyField,
+
+    #[error("Unrecognized weekday '{0}'")]
+    UnknownWeekday(String),
+
+    #[error("Failed to parse calendar value")]
+    ParseValue(#[from] std::num::ParseIntError),
+
+    #[error("Calendar step must be non-zero")]
+    ZeroStep,
+}
+
+/// A single value within a calendar field: either a lone number or a
+/// `start..end` range iterated in `step` increments (step `1` for a plain
+/// range). `7..17/2` becomes `Range { 7, 17, 2 }` (7,9,11,13,15,17) and
+/// `*/15` over minutes becomes `Range { 0, 59, 15 }` (0,15,30,45).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DateTimeValue {
+    Single(u32),
+    Range { start: u32, end: u32, step: u32 },
+}
+
+impl DateTimeValue {
+    fn matches(&self, value: u32) -> bool {
+        match self {
+            DateTimeValue::Single(n) => *n == value,
+            DateTimeValue::Range { start, end, step } => {
+                value >= *start && value <= *end && (value - start) % step == 0
+            }
+        }
+    }
+}
+
+/// `true` if the field places no constraint (absent) or any of its values
+/// matches `value`.
+fn field_matches(field: &[DateTimeValue], value: u32) -> bool {
+    field.is_empty() || field.iter().any(|v| v.matches(value))
+}
+
+/// An OnCalendar-style expression restricting when reminders may fire, e.g.
+/// `Mon..Fri 9..17 *:0/15`. Each field is a list of [`DateTimeValue`]s; an
+/// empty field matches anything (so an absent hour means "every hour").
+#[derive(Debug, PartialEq, Eq)]
+pub struct Schedule {
+    pub weekdays: Vec<DateTimeValue>,
+    pub hours: Vec<DateTimeValue>,
+    pub minutes: Vec<DateTimeValue>,
+}
+
+impl Schedule {
+    pub fn parse(expr: &str) -> Result<Schedule, ScheduleError> {
+        let mut weekdays = Vec::new();
+        let mut hours = Vec::new();
+        let mut minutes = Vec::new();
+        let mut hours_set = false;
+
+        for token in expr.split_whitespace() {
+            if token.chars().any(|c| c.is_ascii_alphabetic()) {
+                weekdays = parse_field(token, 6, resolve_weekday)?;
+            } else if let Some((hour, minute)) = token.split_once(':') {
+                // `*` in the hour position leaves any already-parsed hours
+                // intact and otherwise means "every hour".
+                if !hours_set && hour != "*" {
+                    hours = parse_field(hour, 23, resolve_number)?;
+                    hours_set = true;
+                }
+                minutes = parse_field(minute, 59, resolve_number)?;
+            } else if !hours_set {
+                hours = parse_field(token, 23, resolve_number)?;
+                hours_set = true;
+            } else {
+                minutes = parse_field(token, 59, resolve_number)?;
+            }
+        }
+
+        Ok(Schedule {
+            weekdays,
+            hours,
+            minutes,
+        })
+    }
+
+    /// Whether `datetime` satisfies every field of the expression.
+    pub fn matches(&self, datetime: NaiveDateTime) -> bool {
+        let weekday = datetime.weekday().num_days_from_monday();
+        field_matches(&self.weekdays, weekday)
+            && field_matches(&self.hours, datetime.hour())
+            && field_matches(&self.minutes, datetime.minute())
+    }
+
+    /// The soonest matching instant strictly after `datetime`, found by
+    /// incrementing from the least- to the most-significant field. Searches up
+    /// to a year ahead before giving up.
+    pub fn next_after(&self, datetime: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut candidate = (datetime + Duration::minutes(1))
+            .with_second(0)?
+            .with_nanosecond(0)?;
+        for _ in 0..366 * 24 * 60 {
+            if self.matches(candidate) {
+                return Some(candidate);
+            }
+            candidate += Duration::minutes(1);
+        }
+        None
+    }
+}
+
+fn resolve_number(token: &str) -> Result<u32, ScheduleError> {
+    Ok(token.parse()?)
+}
+
+fn resolve_weekday(token: &str) -> Result<u32, ScheduleError> {
+    match token.to_ascii_lowercase().as_ref() {
+        "mon" => Ok(0),
+        "tue" => Ok(1),
+        "wed" => Ok(2),
+        "thu" => Ok(3),
+        "fri" => Ok(4),
+        "sat" => Ok(5),
+        "sun" => Ok(6),
+        _ => Err(ScheduleError::UnknownWeekday(token.to_string())),
+    }
+}
+
+/// Parse a single comma-separated field into its [`DateTimeValue`]s, resolving
+/// endpoints with `resolve` (numbers directly, weekday names by lookup).
+fn parse_field<F>(spec: &str, max: u32, resolve: F) -> Result<Vec<DateTimeValue>, ScheduleError>
+where
+    F: Fn(&str) -> Result<u32, ScheduleError>,
+{
+    let mut values = Vec::new();
+    for part in spec.split(',') {
+        if part.is_empty() {
+            return Err(ScheduleError::EmptyField);
+        }
+
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse()?;
+                if step == 0 {
+                    return Err(ScheduleError::ZeroStep);
+                }
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let value = if range == "*" {
+            DateTimeValue::Range {
+                start: 0,
+                end: max,
+                step: step.unwrap_or(1),
+            }
+        } else if let Some((start, end)) = range.split_once("..") {
+            DateTimeValue::Range {
+                start: resolve(start)?,
+                end: resolve(end)?,
+                step: step.unwrap_or(1),
+            }
+        } else if let Some(step) = step {
+            // A bare value with a step (e.g. `0/15`) runs to the field's max.
+            DateTimeValue::Range {
+                start: resolve(range)?,
+                end: max,
+                step,
+            }
+        } else {
+            DateTimeValue::Single(resolve(range)?)
+        };
+        values.push(value);
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_ranges_and_steps() {
+        let sched = Schedule::parse("Mon..Fri 9..17 *:0/15").unwrap();
+        assert_eq!(
+            sched.weekdays,
+            vec![DateTimeValue::Range {
+                start: 0,
+                end: 4,
+                step: 1
+            }]
+        );
+        assert_eq!(
+            sched.hours,
+            vec![DateTimeValue::Range {
+                start: 9,
+                end: 17,
+                step: 1
+            }]
+        );
+        assert_eq!(
+            sched.minutes,
+            vec![DateTimeValue::Range {
+                start: 0,
+                end: 59,
+                step: 15
+            }]
+        );
+    }
+
+    #[test]
+    fn test_matches_working_hours() {
+        let sched = Schedule::parse("Mon..Fri 9..17 *:0/15").unwrap();
+
+        // 2024-06-03 is a Monday.
+        assert!(sched.matches(at(2024, 6, 3, 9, 0)));
+        assert!(sched.matches(at(2024, 6, 3, 17, 45)));
+        assert!(!sched.matches(at(2024, 6, 3, 9, 7))); // off the 15m grid
+        assert!(!sched.matches(at(2024, 6, 3, 18, 0))); // past working hours
+        assert!(!sched.matches(at(2024, 6, 8, 10, 0))); // Saturday
+    }
+
+    #[test]
+    fn test_absent_hour_means_every_hour() {
+        let sched = Schedule::parse("Mon..Fri *:0").unwrap();
+        assert!(sched.hours.is_empty());
+        assert!(sched.matches(at(2024, 6, 3, 3, 0)));
+        assert!(sched.matches(at(2024, 6, 3, 22, 0)));
+        assert!(!sched.matches(at(2024, 6, 3, 22, 1)));
+    }
+
+    #[test]
+    fn test_zero_step_is_rejected() {
+        assert!(Schedule::parse("*:*/0").is_err());
+    }
+
+    #[test]
+    fn test_next_after_skips_to_window() {
+        let sched = Schedule::parse("Mon..Fri 9..17 *:0/15").unwrap();
+        // Saturday afternoon -> next match is Monday 09:00.
+        let next = sched.next_after(at(2024, 6, 8, 14, 3)).unwrap();
+        assert_eq!(next, at(2024, 6, 10, 9, 0));
+    }
+}